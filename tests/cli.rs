@@ -0,0 +1,45 @@
+//! End-to-end tests that invoke the compiled `split-log` binary directly,
+//! rather than calling `process_log_file` in-process. `main()`'s dispatch
+//! logic (picking which branch of `match opts.output.as_str()` runs
+//! `process_log_file`) isn't exercised by any unit test, so a regression
+//! there -- e.g. a stray second call that reprocesses the whole input --
+//! would pass the unit test suite unnoticed.
+
+use std::io::Read;
+use std::process::Command;
+
+fn write_temp_file(name: &str, contents: &str) -> String {
+    let path = std::env::temp_dir().join(format!("split-log-cli-test-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn shard_path(output_path: &str, bucket: &str) -> String {
+    format!("{output_path}.{bucket}.jsonl.gz")
+}
+
+#[test]
+fn test_binary_writes_each_line_exactly_once() {
+    let lines: Vec<String> = (0..10)
+        .map(|i| format!(r#"{{"asctime": "2021-03-01 00:00:{:02},000", "i": {i}}}"#, i))
+        .collect();
+    let input_path = write_temp_file("once.jsonl", &format!("{}\n", lines.join("\n")));
+    let output_path = std::env::temp_dir().join(format!("split-log-cli-test-{}-once-out", std::process::id()))
+        .to_string_lossy().into_owned();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_split-log"))
+        .args(["-i", &input_path, "-o", &output_path, "--rotation", "never"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let mut contents = String::new();
+    flate2::read::MultiGzDecoder::new(std::fs::File::open(shard_path(&output_path, "all")).unwrap())
+        .read_to_string(&mut contents)
+        .unwrap();
+    let got: Vec<&str> = contents.lines().collect();
+    assert_eq!(got.len(), 10, "each input line must be written exactly once, got {}: {contents:?}", got.len());
+    for (i, line) in got.iter().enumerate() {
+        assert!(line.contains(&format!(r#""i": {i}"#)), "line {i} out of order or duplicated: {line}");
+    }
+}